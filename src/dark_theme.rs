@@ -1,6 +1,6 @@
 #![cfg_attr(not(windows), expect(unused_imports, unused_variables))]
 
-use std::sync::OnceLock;
+use std::sync::{Mutex, RwLock};
 
 use glib::object::IsA;
 use gtk::{
@@ -9,12 +9,73 @@ use gtk::{
 };
 use relm4::gtk;
 
-/// Cached whether the theme is dark or not. Since we don't change the
-/// application settings after startup its best to re-use the same value.
-static IS_DARK: OnceLock<bool> = OnceLock::new();
+/// Cached whether the theme is dark or not. This is populated lazily from
+/// [`dark_light::detect`] and then kept up to date by the theme watcher
+/// installed via [`watch_window_surface`] whenever the OS theme changes
+/// while the app is running.
+static IS_DARK: RwLock<Option<bool>> = RwLock::new(None);
+
+/// All window surfaces that should be kept in sync with the current theme.
+/// Weak references so that closed windows don't keep their surfaces alive.
+static WATCHED_SURFACES: Mutex<Vec<glib::WeakRef<gdk::Surface>>> = Mutex::new(Vec::new());
+
+/// Let the app force a theme instead of always following the OS setting, see
+/// [`set_theme_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeMode {
+    /// Follow `dark_light::detect()`, updated live by the theme watcher.
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+static THEME_MODE: RwLock<ThemeMode> = RwLock::new(ThemeMode::System);
+
+/// Force the app to use a specific theme regardless of what the OS reports,
+/// or go back to following the OS with [`ThemeMode::System`].
+///
+/// Can be called at any point after startup; already-shown windows that were
+/// registered via [`watch_window_surface`] (which [`set_for_window`] does
+/// automatically) are immediately re-themed to match.
+pub fn set_theme_mode(mode: ThemeMode) {
+    let changed = {
+        let mut current = THEME_MODE.write().unwrap();
+        let changed = *current != mode;
+        *current = mode;
+        changed
+    };
+    if changed {
+        retheme_all();
+    }
+}
+
+pub fn theme_mode() -> ThemeMode {
+    *THEME_MODE.read().unwrap()
+}
 
 pub fn is_dark() -> bool {
-    *IS_DARK.get_or_init(|| matches!(dark_light::detect(), Ok(dark_light::Mode::Dark)))
+    match theme_mode() {
+        ThemeMode::Dark => return true,
+        ThemeMode::Light => return false,
+        ThemeMode::System => {}
+    }
+
+    if let Some(value) = *IS_DARK.read().unwrap() {
+        return value;
+    }
+    let value = matches!(dark_light::detect(), Ok(dark_light::Mode::Dark));
+    *IS_DARK.write().unwrap() = Some(value);
+    value
+}
+
+/// Update the cached dark/light value. Returns `true` if the value actually
+/// changed, meaning every watched surface should be re-themed.
+fn set_cached_is_dark(value: bool) -> bool {
+    let mut cache = IS_DARK.write().unwrap();
+    let changed = *cache != Some(value);
+    *cache = Some(value);
+    changed
 }
 
 /// Need to have initialize GTK.
@@ -46,7 +107,9 @@ where
             window.show();
         }
 
-        set_for_window_surface(&window.surface().expect("Can't get surface for window"));
+        let surface = window.surface().expect("Can't get surface for window");
+        set_for_window_surface(&surface);
+        watch_window_surface(&surface);
     }
 }
 /// Need to manually tell Windows that the native title bar can be dark:
@@ -151,3 +214,340 @@ pub fn set_for_window_surface(window: &impl IsA<gtk::gdk::Surface>) {
         }
     }
 }
+
+/// System backdrop materials available on Windows 11, see
+/// `DWM_SYSTEMBACKDROP_TYPE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backdrop {
+    /// Let DWM decide, based on the window type.
+    Auto,
+    /// No backdrop material, just the plain window background.
+    None,
+    /// Mica, the backdrop normally used by app windows.
+    Mica,
+    /// Acrylic, the backdrop normally used by transient windows/flyouts.
+    Acrylic,
+    /// Tabbed Mica, the backdrop normally used by tabbed windows.
+    TabbedMica,
+}
+
+/// Request a Windows 11 system backdrop material (Mica/Acrylic/Tabbed Mica)
+/// for `window` via `DWMWA_SYSTEMBACKDROP_TYPE` (attribute 38, Windows 11
+/// build 22621+).
+///
+/// For the backdrop to actually show through, the GTK window's own
+/// background needs to be made transparent (e.g. via CSS), see
+/// [`extend_frame_into_client_area`] if the client area also needs to be
+/// extended under a custom-drawn title bar. No-ops (returns `false`) on
+/// older Windows builds where the `DwmSetWindowAttribute` call fails, or on
+/// non-Windows platforms.
+pub fn set_backdrop_for_window_surface(
+    window: &impl IsA<gtk::gdk::Surface>,
+    backdrop: Backdrop,
+) -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Graphics::Dwm::{
+            DwmSetWindowAttribute, DWMSBT_AUTO, DWMSBT_MAINWINDOW, DWMSBT_NONE,
+            DWMSBT_TABBEDWINDOW, DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
+        };
+
+        let value = match backdrop {
+            Backdrop::Auto => DWMSBT_AUTO,
+            Backdrop::None => DWMSBT_NONE,
+            Backdrop::Mica => DWMSBT_MAINWINDOW,
+            Backdrop::Acrylic => DWMSBT_TRANSIENTWINDOW,
+            Backdrop::TabbedMica => DWMSBT_TABBEDWINDOW,
+        };
+
+        let handle = gdk_win32::Win32Surface::impl_hwnd(window);
+        unsafe {
+            DwmSetWindowAttribute(
+                handle,
+                DWMWA_SYSTEMBACKDROP_TYPE,
+                &value as *const _ as *const _,
+                std::mem::size_of_val(&value) as u32,
+            )
+            .is_ok()
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (window, backdrop);
+        false
+    }
+}
+
+/// Extend the window frame into the client area, letting a system backdrop
+/// (see [`set_backdrop_for_window_surface`]) show through areas that would
+/// otherwise be painted over by GTK, e.g. behind a custom-drawn title bar.
+/// `margins` follow `DwmExtendFrameIntoClientArea`'s `(left, right, top,
+/// bottom)` order; use `-1` for a margin to extend it over the whole
+/// corresponding side.
+pub fn extend_frame_into_client_area(window: &impl IsA<gtk::gdk::Surface>, margins: (i32, i32, i32, i32)) {
+    #[cfg(windows)]
+    {
+        use windows::Win32::{Graphics::Dwm::DwmExtendFrameIntoClientArea, UI::Controls::MARGINS};
+
+        let (left, right, top, bottom) = margins;
+        let margins = MARGINS {
+            cxLeftWidth: left,
+            cxRightWidth: right,
+            cyTopHeight: top,
+            cyBottomHeight: bottom,
+        };
+        let handle = gdk_win32::Win32Surface::impl_hwnd(window);
+        unsafe {
+            let _ = DwmExtendFrameIntoClientArea(handle, &margins);
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (window, margins);
+    }
+}
+
+/// Pack an `(r, g, b)` color into the `0x00BBGGRR` `COLORREF` layout that DWM
+/// expects.
+#[cfg(windows)]
+fn pack_colorref(rgb: (u8, u8, u8)) -> u32 {
+    let (r, g, b) = rgb;
+    u32::from(r) | (u32::from(g) << 8) | (u32::from(b) << 16)
+}
+
+/// Sentinel passed instead of a `COLORREF` to reset an attribute back to its
+/// default, see `DWMWA_COLOR_DEFAULT`.
+#[cfg(windows)]
+const DWMWA_COLOR_DEFAULT: u32 = 0xFFFF_FFFF;
+
+/// Set the native title bar's caption (background) color via
+/// `DWMWA_CAPTION_COLOR` (attribute 35, Windows 11+). Pass `None` to reset
+/// it to `DWMWA_COLOR_DEFAULT`. No-ops on older Windows builds.
+pub fn set_caption_color(window: &impl IsA<gtk::gdk::Surface>, rgb: Option<(u8, u8, u8)>) -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_CAPTION_COLOR};
+
+        let value = rgb.map(pack_colorref).unwrap_or(DWMWA_COLOR_DEFAULT);
+        let handle = gdk_win32::Win32Surface::impl_hwnd(window);
+        unsafe {
+            DwmSetWindowAttribute(
+                handle,
+                DWMWA_CAPTION_COLOR,
+                &value as *const u32 as *const _,
+                std::mem::size_of_val(&value) as u32,
+            )
+            .is_ok()
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (window, rgb);
+        false
+    }
+}
+
+/// Set the native title bar's text color via `DWMWA_TEXT_COLOR` (attribute
+/// 36, Windows 11+). Pass `None` to reset it to `DWMWA_COLOR_DEFAULT`.
+pub fn set_title_text_color(window: &impl IsA<gtk::gdk::Surface>, rgb: Option<(u8, u8, u8)>) -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_TEXT_COLOR};
+
+        let value = rgb.map(pack_colorref).unwrap_or(DWMWA_COLOR_DEFAULT);
+        let handle = gdk_win32::Win32Surface::impl_hwnd(window);
+        unsafe {
+            DwmSetWindowAttribute(
+                handle,
+                DWMWA_TEXT_COLOR,
+                &value as *const u32 as *const _,
+                std::mem::size_of_val(&value) as u32,
+            )
+            .is_ok()
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (window, rgb);
+        false
+    }
+}
+
+/// Set the window border color via `DWMWA_BORDER_COLOR` (attribute 34,
+/// Windows 11+). Pass `None` to reset it to `DWMWA_COLOR_DEFAULT`.
+pub fn set_border_color(window: &impl IsA<gtk::gdk::Surface>, rgb: Option<(u8, u8, u8)>) -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_BORDER_COLOR};
+
+        let value = rgb.map(pack_colorref).unwrap_or(DWMWA_COLOR_DEFAULT);
+        let handle = gdk_win32::Win32Surface::impl_hwnd(window);
+        unsafe {
+            DwmSetWindowAttribute(
+                handle,
+                DWMWA_BORDER_COLOR,
+                &value as *const u32 as *const _,
+                std::mem::size_of_val(&value) as u32,
+            )
+            .is_ok()
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (window, rgb);
+        false
+    }
+}
+
+/// Register `surface` so that [`retheme_all`] re-applies the
+/// dark-mode attribute to it whenever the OS theme changes, and install a
+/// window-message hook that watches for that change.
+///
+/// Safe to call more than once for the same surface; already-registered
+/// surfaces are skipped.
+pub fn watch_window_surface(surface: &impl IsA<gtk::gdk::Surface>) {
+    #[cfg(windows)]
+    {
+        let surface = surface.clone().upcast();
+        {
+            let mut watched = WATCHED_SURFACES.lock().unwrap();
+            if watched
+                .iter()
+                .any(|weak| weak.upgrade().as_ref() == Some(&surface))
+            {
+                return;
+            }
+            let weak = glib::WeakRef::new();
+            weak.set(Some(&surface));
+            watched.push(weak);
+        }
+
+        windows_theme_watcher::install(&surface);
+    }
+}
+
+/// Stop tracking `window`. Its surface is dropped from the registry used by
+/// [`retheme_all`] and the live OS theme watcher; call this when the window
+/// is closed so the registry doesn't grow without bound.
+pub fn unregister_window<W>(window: &W)
+where
+    W: IsA<gtk::Window>,
+{
+    #[cfg(windows)]
+    {
+        if let Some(surface) = W::clone(window).upcast::<gtk::Window>().surface() {
+            WATCHED_SURFACES
+                .lock()
+                .unwrap()
+                .retain(|weak| weak.upgrade().as_ref() != Some(&surface));
+        }
+    }
+}
+
+/// Re-apply the current theme (the OS setting, or the [`ThemeMode`] override)
+/// to every window registered via [`set_for_window`]/[`watch_window_surface`],
+/// dropping any surfaces that have since been closed.
+///
+/// Idempotent, so it's safe to call this unconditionally whenever the theme
+/// might need refreshing, e.g. from the live OS theme watcher, after
+/// [`set_theme_mode`], or on window focus-in/activation to make sure the
+/// active-vs-inactive title bar is painted with the right theme after the
+/// window was first created (DWM can otherwise briefly paint a fresh window
+/// with a light title bar before the attribute takes effect).
+pub fn retheme_all() {
+    // Keeps every GTK-rendered widget (not just the native Win32 title bar
+    // handled below) in sync, so a live OS theme toggle or a `set_theme_mode`
+    // override actually repaints the whole window, not just its title bar.
+    if let Some(display) = gdk::Display::default() {
+        gtk::Settings::for_display(&display).set_gtk_application_prefer_dark_theme(is_dark());
+    }
+
+    let surfaces: Vec<_> = {
+        let mut watched = WATCHED_SURFACES.lock().unwrap();
+        watched.retain(|weak| weak.upgrade().is_some());
+        watched.iter().filter_map(|weak| weak.upgrade()).collect()
+    };
+    for surface in surfaces {
+        set_for_window_surface(&surface);
+    }
+}
+
+#[cfg(windows)]
+mod windows_theme_watcher {
+    //! Subclasses each watched top-level window so we notice `WM_SETTINGCHANGE`
+    //! messages and react to live OS theme changes (e.g. the user flips
+    //! Windows between light and dark mode while the app is running).
+
+    use gtk::gdk;
+    use windows::{
+        core::PCWSTR,
+        Win32::{
+            Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+            System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+            UI::{
+                Controls::{DefSubclassProc, SetWindowSubclass},
+                WindowsAndMessaging::WM_SETTINGCHANGE,
+            },
+        },
+    };
+
+    use super::{set_cached_is_dark, retheme_all};
+
+    const THEME_PERSONALIZE_KEY: PCWSTR = windows::core::w!(
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"
+    );
+    const APPS_USE_LIGHT_THEME_VALUE: PCWSTR = windows::core::w!("AppsUseLightTheme");
+
+    /// Install the subclass that watches for theme changes on `surface`'s
+    /// top-level window. No-op if the surface has no backing `HWND`.
+    pub(super) fn install(surface: &gdk::Surface) {
+        let handle = gdk_win32::Win32Surface::impl_hwnd(surface);
+        unsafe {
+            // `0` as the subclass id since we only ever install this single
+            // subclass per window.
+            let _ = SetWindowSubclass(handle, Some(subclass_proc), 0, 0);
+        }
+    }
+
+    /// Read `HKCU\...\Personalize\AppsUseLightTheme`; `0` means dark mode.
+    fn read_is_dark_from_registry() -> bool {
+        let mut value: u32 = 1;
+        let mut size = std::mem::size_of_val(&value) as u32;
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                THEME_PERSONALIZE_KEY,
+                APPS_USE_LIGHT_THEME_VALUE,
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut value as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+        // Default to light mode if the key is missing, matching Windows' own
+        // fallback behavior.
+        result.is_ok() && value == 0
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id_subclass: usize,
+        _ref_data: usize,
+    ) -> LRESULT {
+        if msg == WM_SETTINGCHANGE && lparam.0 != 0 {
+            // lParam points to the UTF-16 string "ImmersiveColorSet" when the
+            // system theme (light/dark) was toggled.
+            let setting = unsafe { PCWSTR(lparam.0 as *const u16).to_string() };
+            if setting.as_deref() == Ok("ImmersiveColorSet") {
+                let is_dark = read_is_dark_from_registry();
+                if set_cached_is_dark(is_dark) {
+                    retheme_all();
+                }
+            }
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
@@ -1,18 +1,45 @@
 mod actions;
 #[cfg(feature = "dark-theme")]
 mod dark_theme;
+mod link_rules;
+pub mod plugin;
+#[cfg(unix)]
+mod portal;
+mod recent_files;
+#[cfg(all(unix, feature = "search-provider"))]
+mod search_provider;
+mod smart_summary;
 
 use std::{any::Any, future::Future, path::PathBuf, pin::Pin, time::Duration};
 
 use actions::{FileData, FileInfo};
 use gio::ApplicationFlags;
-use gtk::{gdk, gio, prelude::*};
+use gtk::{gdk, gio, glib, prelude::*};
 use relm4::{
     gtk,
     prelude::{DynamicIndex, FactoryComponent, FactoryVecDeque},
     Component, ComponentParts, ComponentSender, FactorySender, RelmApp, RelmWidgetExt,
 };
 
+/// Actions offered by a sidebar row's right-click context menu. Only
+/// meaningful for rows in [`AppModel::sidebar_list_items`]; rows reused for
+/// the wizard's profile list leave [`RowLabelModel::has_context_menu`]
+/// unset, so right-click does nothing there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowContextAction {
+    ExportOnlyThisWindow,
+    CopyThisWindowsLinks,
+    RenameGroup,
+    SelectAllTabsFromGroup,
+}
+
+/// Emitted by a [`RowLabelModel`] row; carries its own index so the parent
+/// knows which row the action applies to without re-deriving it.
+#[derive(Debug)]
+enum RowLabelOutput {
+    ContextAction(DynamicIndex, RowContextAction),
+}
+
 #[derive(Debug)]
 struct RowLabelModel {
     pub name: String,
@@ -20,6 +47,14 @@ struct RowLabelModel {
     pub selectable: bool,
     pub opacity: f64,
     pub data: Box<dyn Any>,
+    /// Whether right-clicking this row should open the sidebar row context
+    /// menu (see [`RowContextAction`]). Only set for the window/group rows
+    /// pushed into `AppModel::sidebar_list_items`; rows shared with the
+    /// wizard's profile list (and header/separator rows) leave this `false`.
+    pub has_context_menu: bool,
+    /// Set by `init_model`. `None` until then, which never happens in
+    /// practice since the factory always calls `init_model` first.
+    index: Option<DynamicIndex>,
 }
 impl Default for RowLabelModel {
     fn default() -> Self {
@@ -29,6 +64,8 @@ impl Default for RowLabelModel {
             selectable: true,
             opacity: 1.0,
             data: Box::new(()),
+            has_context_menu: false,
+            index: None,
         }
     }
 }
@@ -36,7 +73,7 @@ impl Default for RowLabelModel {
 impl FactoryComponent for RowLabelModel {
     type Init = RowLabelModel;
     type Input = ();
-    type Output = ();
+    type Output = RowLabelOutput;
     type CommandOutput = ();
     type ParentWidget = gtk::ListBox;
 
@@ -45,6 +82,28 @@ impl FactoryComponent for RowLabelModel {
         gtk::ListBoxRow {
             set_selectable: self.selectable,
             set_activatable: self.activatable,
+            insert_action_group: (
+                "row",
+                self.has_context_menu
+                    .then(|| build_row_action_group(&sender, self.index.clone()))
+                    .as_ref(),
+            ),
+
+            #[name = "context_menu"]
+            gtk::PopoverMenu::from_model(Some(&row_context_menu_model())) {
+                set_has_arrow: false,
+            },
+
+            add_controller = gtk::GestureClick {
+                set_button: 3,
+                connect_released[context_menu, has_context_menu = self.has_context_menu] => move |gesture, _, _, _| {
+                    if has_context_menu {
+                        gesture.set_state(gtk::EventSequenceState::Claimed);
+                        context_menu.popup();
+                    }
+                }
+            },
+
             gtk::Label {
                 set_label: &self.name,
                 set_opacity: self.opacity,
@@ -52,16 +111,132 @@ impl FactoryComponent for RowLabelModel {
         }
     }
 
-    fn init_model(value: Self::Init, _index: &DynamicIndex, _sender: FactorySender<Self>) -> Self {
+    fn init_model(mut value: Self::Init, index: &DynamicIndex, _sender: FactorySender<Self>) -> Self {
+        value.index = Some(index.clone());
         value
     }
 
     fn update(&mut self, _msg: Self::Input, _sender: FactorySender<Self>) {}
 }
 
+/// Build the `gio::SimpleActionGroup` backing a sidebar row's context menu
+/// (see [`row_context_menu_model`]), emitting a [`RowLabelOutput`] carrying
+/// `index` for whichever action the user picks.
+fn build_row_action_group(
+    sender: &FactorySender<RowLabelModel>,
+    index: Option<DynamicIndex>,
+) -> gio::SimpleActionGroup {
+    let actions = gio::SimpleActionGroup::new();
+    for (name, action) in [
+        ("export-window", RowContextAction::ExportOnlyThisWindow),
+        ("copy-links", RowContextAction::CopyThisWindowsLinks),
+        ("rename-group", RowContextAction::RenameGroup),
+        ("select-group", RowContextAction::SelectAllTabsFromGroup),
+    ] {
+        let simple_action = gio::SimpleAction::new(name, None);
+        simple_action.connect_activate({
+            let sender = sender.clone();
+            let index = index.clone();
+            move |_, _| {
+                if let Some(index) = index.clone() {
+                    sender
+                        .output_sender()
+                        .emit(RowLabelOutput::ContextAction(index, action));
+                }
+            }
+        });
+        actions.add_action(&simple_action);
+    }
+    actions
+}
+
+/// Menu model shown by each sidebar row's right-click context menu.
+fn row_context_menu_model() -> gio::Menu {
+    let menu = gio::Menu::new();
+    menu.append(Some("Export only this window…"), Some("row.export-window"));
+    menu.append(Some("Copy this window's links"), Some("row.copy-links"));
+    menu.append(Some("Rename group"), Some("row.rename-group"));
+    menu.append(
+        Some("Select all tabs from this group"),
+        Some("row.select-group"),
+    );
+    menu
+}
+
 /// A boxed future that implements `Send`.
 type BoxedFuture<T = ()> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
 
+/// Progress of whatever long-running operation is currently in flight, shown
+/// via a `gtk::ProgressBar` next to the status [`gtk::Entry`].
+#[derive(Debug, Clone, Default, PartialEq)]
+enum Progress {
+    /// Nothing is in progress; hide the progress bar.
+    #[default]
+    Idle,
+    /// An operation with no countable steps is running (pulses the bar).
+    Indeterminate,
+    /// A countable operation is running; `fraction` (`0.0..=1.0`) is how far
+    /// through its steps it has got. The load pipeline sets it as each phase
+    /// starts, see [`LOAD_PIPELINE_PHASES`].
+    Fraction(f64),
+}
+
+/// Number of sequential, always-run phases in the load pipeline that
+/// [`Progress::Fraction`] counts through once [`AppInputMsg::LoadNewData`]
+/// starts: reading the file, parsing the session data, finding its tab
+/// groups, and generating the preview. Decompression is an extra phase
+/// that only runs for compressed input and whose own duration isn't
+/// countable, so it reports [`Progress::Indeterminate`] instead of
+/// advancing this fraction.
+const LOAD_PIPELINE_PHASES: f64 = 4.0;
+
+/// Window widths below this switch from the wide (sidebar beside content)
+/// layout to the narrow (sidebar above content) layout.
+const NARROW_LAYOUT_WIDTH_THRESHOLD: i32 = 700;
+
+/// Which "place" the profile wizard's list is currently showing, picked via
+/// its places sidebar, see [`AppInputMsg::WizardPlaceSelected`]. Variant
+/// order must match the row order of the `gtk::ListBox` built in
+/// `AppModel::init` (row 0 is `InstalledProfiles`, and so on) — that
+/// `connect_row_selected` handler maps `row.index()` to a variant by hand
+/// since rows aren't backed by a factory over this enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum WizardPlace {
+    #[default]
+    InstalledProfiles,
+    RecentSessionFiles,
+    BackupLocations,
+}
+
+/// Whether `name` or `path` match the profile wizard's typeahead `filter`
+/// text (already lowercased), see [`AppInputMsg::WizardFilterChanged`].
+fn wizard_entry_matches_filter(name: &str, path: &std::path::Path, filter: &str) -> bool {
+    filter.is_empty()
+        || name.to_lowercase().contains(filter)
+        || path.to_string_lossy().to_lowercase().contains(filter)
+}
+
+/// Every file under an installed Firefox profile's `sessionstore-backups/`
+/// directory, for the profile wizard's [`WizardPlace::BackupLocations`].
+/// Firefox drops periodic/upgrade/crash-recovery backups there alongside
+/// the profile's live session file.
+fn firefox_backup_session_files() -> Vec<PathBuf> {
+    actions::FirefoxProfileInfo::all_profiles()
+        .into_iter()
+        .filter_map(|profile| {
+            let sessionstore = profile.find_sessionstore_file();
+            sessionstore.parent().map(|dir| dir.join("sessionstore-backups"))
+        })
+        .flat_map(|backups_dir| {
+            std::fs::read_dir(&backups_dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+        })
+        .collect()
+}
+
 struct AppModel {
     sidebar_indexes: Vec<i32>,
     /// Index of last activated sidebar item. Used when handling
@@ -78,9 +253,42 @@ struct AppModel {
     output_path: PathBuf,
     output_format: actions::FormatInfo,
     status: String,
+    /// Progress of the current load/parse/export operation, if any.
+    progress: Progress,
+    /// Timer re-pulsing `progress_bar` while [`Progress::Indeterminate`] is
+    /// active; a single `pulse()` call only nudges the bar once, so this is
+    /// what actually makes it animate instead of freezing mid-operation. See
+    /// [`AppModel::start_progress_pulse`]/[`AppModel::stop_progress_pulse`].
+    progress_pulse_source: Option<glib::SourceId>,
     is_showing_wizard: bool,
     wizard_profiles: FactoryVecDeque<RowLabelModel>,
+    /// Which places-sidebar entry `wizard_profiles` is currently populated
+    /// from.
+    wizard_place: WizardPlace,
+    /// Current typeahead text from the wizard's filter entry.
+    wizard_filter: String,
+    /// Portal-granted Firefox profile location used as an extra
+    /// [`WizardPlace::InstalledProfiles`] entry when direct enumeration
+    /// finds nothing (e.g. inside a sandbox), see
+    /// [`portal::prompt_profile_fallback`].
+    wizard_profile_fallback: Option<PathBuf>,
     background_worker: tokio::sync::mpsc::UnboundedSender<BoxedFuture>,
+
+    /// Whether a Smart Summary request is currently in flight; used to
+    /// disable the button and show a spinner while we wait.
+    smart_summary_busy: bool,
+    /// Incremented every [`AppInputMsg::RequestSmartSummary`]; stashed in
+    /// the matching [`AppCommandMsg::SmartSummaryResult`] so a result from
+    /// an older request can't clobber `smart_summary_busy`/`status` set by
+    /// one requested after it.
+    smart_summary_request_id: u64,
+    /// Whether a Smart Summary endpoint is configured at all; hides the
+    /// button entirely when it isn't, see [`smart_summary::Config::from_env`].
+    smart_summary_enabled: bool,
+
+    /// Whether the sidebar is shown beside the content (`true`) or stacked
+    /// above it (`false`), see [`AppInputMsg::WindowWidthChanged`].
+    wide_layout: bool,
 }
 impl AppModel {
     /// Set a path to a user input text. This should be careful with updating
@@ -106,6 +314,7 @@ impl AppModel {
                 list_items.push_back(RowLabelModel {
                     name: group.name.clone(),
                     data: Box::new((true, group.clone())),
+                    has_context_menu: true,
                     ..Default::default()
                 });
             }
@@ -126,11 +335,74 @@ impl AppModel {
                 list_items.push_back(RowLabelModel {
                     name: group.name.clone(),
                     data: Box::new((false, group.clone())),
+                    has_context_menu: true,
                     ..Default::default()
                 });
             }
         }
     }
+    /// Rebuild `wizard_profiles` for the currently selected `wizard_place`,
+    /// keeping only entries matching `wizard_filter`.
+    fn populate_wizard_profiles(&mut self) {
+        let filter = self.wizard_filter.to_lowercase();
+        let mut profiles = self.wizard_profiles.guard();
+        profiles.clear();
+
+        match self.wizard_place {
+            WizardPlace::InstalledProfiles => {
+                // Extra entry from the sandboxed fallback, see
+                // `wizard_profile_fallback`'s doc comment.
+                if let Some(path) = self.wizard_profile_fallback.clone() {
+                    let name = path.display().to_string();
+                    if wizard_entry_matches_filter(&name, &path, &filter) {
+                        profiles.push_back(RowLabelModel {
+                            name,
+                            data: Box::new(path),
+                            ..Default::default()
+                        });
+                    }
+                }
+                for profile in actions::FirefoxProfileInfo::all_profiles() {
+                    let name = profile.name().into_owned();
+                    let path = profile.find_sessionstore_file();
+                    if !wizard_entry_matches_filter(&name, &path, &filter) {
+                        continue;
+                    }
+                    profiles.push_back(RowLabelModel {
+                        name,
+                        data: Box::new(profile),
+                        ..Default::default()
+                    });
+                }
+            }
+            WizardPlace::RecentSessionFiles => {
+                for path in recent_files::list() {
+                    let name = path.display().to_string();
+                    if !wizard_entry_matches_filter(&name, &path, &filter) {
+                        continue;
+                    }
+                    profiles.push_back(RowLabelModel {
+                        name,
+                        data: Box::new(path),
+                        ..Default::default()
+                    });
+                }
+            }
+            WizardPlace::BackupLocations => {
+                for path in firefox_backup_session_files() {
+                    let name = path.display().to_string();
+                    if !wizard_entry_matches_filter(&name, &path, &filter) {
+                        continue;
+                    }
+                    profiles.push_back(RowLabelModel {
+                        name,
+                        data: Box::new(path),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
     /// The currently selected windows or tab groups that the user want to
     /// preview and later write to a file.
     fn selected_tab_groups(
@@ -160,6 +432,30 @@ impl AppModel {
         options
     }
 
+    /// Start (or restart) the timer that repeatedly pulses `progress_bar`
+    /// while [`Progress::Indeterminate`] is active. A single `pulse()` call
+    /// only nudges the bar once, so an operation with no countable steps
+    /// needs this to actually look alive instead of freezing for its full
+    /// duration; call [`Self::stop_progress_pulse`] once the phase ends.
+    fn start_progress_pulse(&mut self, widgets: &<Self as Component>::Widgets) {
+        self.stop_progress_pulse();
+        let progress_bar = widgets.progress_bar.clone();
+        self.progress_pulse_source = Some(glib::timeout_add_local(
+            Duration::from_millis(100),
+            move || {
+                progress_bar.pulse();
+                glib::ControlFlow::Continue
+            },
+        ));
+    }
+
+    /// Cancel a timer started by [`Self::start_progress_pulse`], if any.
+    fn stop_progress_pulse(&mut self) {
+        if let Some(source) = self.progress_pulse_source.take() {
+            source.remove();
+        }
+    }
+
     /// Queue some work to be preformed later. The queued work will not be
     /// executed if a newer action is queued.
     fn queue_background_work(&self, work: impl Future<Output = ()> + Send + 'static) {
@@ -190,12 +486,29 @@ impl AppModel {
 #[derive(Debug)]
 enum AppInputMsg {
     WindowShow,
+    /// The window's default width changed; used to flip between the wide
+    /// (sidebar beside content) and narrow (sidebar above content) layouts.
+    WindowWidthChanged(i32),
     SidebarRowSelected(i32),
     SidebarRowsChanged,
     EditedInputPath,
+    /// A row's right-click context menu action was triggered for the row at
+    /// `DynamicIndex` in [`AppModel::sidebar_list_items`].
+    SidebarRowContextAction(DynamicIndex, RowContextAction),
+    /// The user confirmed a new name in the rename-group popover opened by
+    /// [`RowContextAction::RenameGroup`]. Only relabels the sidebar row:
+    /// `actions::GenerateOptions` has no field to carry a name override, so
+    /// the preview and any exported file keep using the original group name
+    /// from `loaded_data`.
+    RenameGroupCommitted(DynamicIndex, String),
     OpenWizard,
     CloseWizard,
     SelectedWizardProfile(i32),
+    /// The profile wizard's places sidebar selected a different source of
+    /// entries for `wizard_profiles`.
+    WizardPlaceSelected(WizardPlace),
+    /// The profile wizard's typeahead filter entry changed.
+    WizardFilterChanged(String),
     BrowseInput,
     LoadNewData,
     EditedOutputPath,
@@ -204,6 +517,12 @@ enum AppInputMsg {
     CopyLinksToClipboard,
     SaveLinksToFile,
     PreviewChanged,
+    /// Ask the configured Smart Summary endpoint to name the currently
+    /// selected tab group(s), see [`smart_summary`].
+    RequestSmartSummary,
+    /// GNOME Shell's `LaunchSearch` asked to focus the app window with
+    /// `query` in mind, see [`search_provider`].
+    FocusWithQuery(String),
 }
 /// These messages are generated by background tasks.
 #[derive(Debug)]
@@ -216,6 +535,23 @@ enum AppCommandMsg {
     SetPreview(String),
     SetStatus(String),
     FixPreviewScrollbar,
+    /// Write `String` to the clipboard. Needs to run on the main thread, so
+    /// background work that generates the text emits this instead of
+    /// touching the clipboard itself.
+    CopyTextToClipboard(String),
+    /// Result of the Smart Summary request with the given
+    /// `smart_summary_request_id`, ignored if a newer request has since
+    /// been made. `Ok(None)` means no Smart Summary endpoint is configured.
+    SmartSummaryResult(u64, Result<Option<String>, String>),
+    /// Tell registered plugins that an export finished, see
+    /// [`plugin::AppEvent::ExportCompleted`].
+    NotifyExportCompleted,
+    /// A sandboxed profile location was picked through the portal, see
+    /// `AppModel::wizard_profile_fallback`.
+    SetWizardProfileFallback(PathBuf),
+    /// Rebuild the GNOME Shell search provider's index from the rendered
+    /// markdown links text for every tab group, see [`search_provider`].
+    UpdateSearchIndex(String),
 }
 
 #[relm4::component]
@@ -234,6 +570,9 @@ impl Component for AppModel {
             set_default_height: 700,
 
             connect_show => AppInputMsg::WindowShow,
+            connect_default_width_notify[sender] => move |window| {
+                sender.input(AppInputMsg::WindowWidthChanged(window.default_width()));
+            },
 
             #[name = "wizard_container"]
             gtk::Overlay {
@@ -276,35 +615,101 @@ impl Component for AppModel {
                             add_css_class: "wizard-header",
                         },
                         gtk::Box {
-                            set_orientation: gtk::Orientation::Vertical,
-                            gtk::Label {
-                                set_label: "Firefox Profiles:",
-                                set_halign: gtk::Align::Start,
-                                set_margin_top: 10,
-                                set_margin_bottom: 5,
+                            set_orientation: gtk::Orientation::Horizontal,
+
+                            #[name = "wizard_places_list"]
+                            gtk::ListBox {
+                                set_width_request: 150,
+                                set_selection_mode: gtk::SelectionMode::Browse,
+                                add_css_class: "wizard-places-sidebar",
+
+                                // Row order must match `WizardPlace`'s
+                                // variant order (see its doc comment); a row
+                                // index outside that known set is ignored
+                                // rather than silently mapped to whichever
+                                // arm used to be the catch-all.
+                                connect_row_selected[sender] => move |_list, row| {
+                                    if let Some(row) = row {
+                                        let place = match row.index() {
+                                            0 => WizardPlace::InstalledProfiles,
+                                            1 => WizardPlace::RecentSessionFiles,
+                                            2 => WizardPlace::BackupLocations,
+                                            _ => return,
+                                        };
+                                        sender.input(AppInputMsg::WizardPlaceSelected(place));
+                                    }
+                                },
+
+                                gtk::Label {
+                                    set_label: "Installed Profiles",
+                                    set_halign: gtk::Align::Start,
+                                    set_margin_all: 6,
+                                },
+                                gtk::Label {
+                                    set_label: "Recent Session Files",
+                                    set_halign: gtk::Align::Start,
+                                    set_margin_all: 6,
+                                },
+                                gtk::Label {
+                                    set_label: "Session Backups",
+                                    set_halign: gtk::Align::Start,
+                                    set_margin_all: 6,
+                                },
                             },
 
-                            gtk::ScrolledWindow {
-                                set_vexpand: true,
-                                set_valign: gtk::Align::Fill,
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_hexpand: true,
 
-                                #[local_ref]
-                                wizard_profile_list -> gtk::ListBox {
-                                    set_width_request: 200,
-                                    set_activate_on_single_click: true,
-                                    set_selection_mode: gtk::SelectionMode::Browse,
+                                gtk::Label {
+                                    #[watch]
+                                    set_label: match model.wizard_place {
+                                        WizardPlace::InstalledProfiles => "Firefox Profiles:",
+                                        WizardPlace::RecentSessionFiles => "Recent Session Files:",
+                                        WizardPlace::BackupLocations => "Session Backups:",
+                                    },
+                                    set_halign: gtk::Align::Start,
+                                    set_margin_top: 10,
+                                    set_margin_bottom: 5,
+                                },
 
-                                    connect_row_activated[sender] => move |_list, row| {
-                                        sender.input(AppInputMsg::SelectedWizardProfile(row.index()));
-                                        sender.input(AppInputMsg::CloseWizard);
+                                #[name = "wizard_filter_entry"]
+                                gtk::SearchEntry {
+                                    set_placeholder_text: Some("Filter by name or path…"),
+                                    connect_search_changed[sender] => move |entry| {
+                                        sender.input(AppInputMsg::WizardFilterChanged(entry.text().into()));
                                     },
                                 },
+
+                                gtk::ScrolledWindow {
+                                    set_vexpand: true,
+                                    set_valign: gtk::Align::Fill,
+
+                                    #[local_ref]
+                                    wizard_profile_list -> gtk::ListBox {
+                                        set_width_request: 200,
+                                        set_activate_on_single_click: true,
+                                        set_selection_mode: gtk::SelectionMode::Browse,
+
+                                        connect_row_activated[sender] => move |_list, row| {
+                                            sender.input(AppInputMsg::SelectedWizardProfile(row.index()));
+                                            sender.input(AppInputMsg::CloseWizard);
+                                        },
+                                    },
+                                }
                             }
                         }
                     }
                 },
                 gtk::Box {
-                    set_orientation: gtk::Orientation::Horizontal,
+                    // Side by side on wide windows, stacked on narrow ones,
+                    // see `AppInputMsg::WindowWidthChanged`.
+                    #[watch]
+                    set_orientation: if model.wide_layout {
+                        gtk::Orientation::Horizontal
+                    } else {
+                        gtk::Orientation::Vertical
+                    },
                     set_spacing: 5,
                     set_margin_all: 5,
 
@@ -445,12 +850,41 @@ impl Component for AppModel {
                                 connect_clicked => AppInputMsg::CopyLinksToClipboard,
                             },
 
+                            #[name = "smart_summary_button"]
+                            gtk::Button {
+                                set_label: "Smart Summary",
+                                set_visible: model.smart_summary_enabled,
+                                #[watch]
+                                set_sensitive: !model.smart_summary_busy,
+                                connect_clicked => AppInputMsg::RequestSmartSummary,
+                            },
+                            #[name = "smart_summary_spinner"]
+                            gtk::Spinner {
+                                set_visible: model.smart_summary_enabled,
+                                #[watch]
+                                set_spinning: model.smart_summary_busy,
+                            },
+
+                            // Slot where plugins can inject their own toolbar widgets,
+                            // see `plugin::UiSlot::Toolbar`.
+                            #[name = "plugin_toolbar_slot"]
+                            gtk::Box {
+                                set_spacing: 5,
+                            },
+
                             gtk::Box {
                                 // This is used as a "spacer" to align the buttons to the right
                                 set_hexpand: true,
                                 set_halign: gtk::Align::Fill,
                             },
 
+                            // Slot where plugins can inject a widget next to the output
+                            // format dropdown, see `plugin::UiSlot::OutputOptions`.
+                            #[name = "plugin_output_options_slot"]
+                            gtk::Box {
+                                set_spacing: 5,
+                            },
+
                             #[name = "output_format"]
                             gtk::DropDown::from_strings(
                                 &actions::FormatInfo::all()
@@ -489,6 +923,12 @@ impl Component for AppModel {
                                 set_editable: false,
                                 #[watch]
                                 set_text: &model.status,
+                            },
+                            #[name = "progress_bar"]
+                            gtk::ProgressBar {
+                                set_width_request: 100,
+                                #[watch]
+                                set_visible: model.progress != Progress::Idle,
                             }
                         }
                     }
@@ -524,7 +964,11 @@ impl Component for AppModel {
 
         let sidebar_list_items: FactoryVecDeque<RowLabelModel> = FactoryVecDeque::builder()
             .launch(gtk::ListBox::default())
-            .detach();
+            .forward(sender.input_sender(), |output| match output {
+                RowLabelOutput::ContextAction(index, action) => {
+                    AppInputMsg::SidebarRowContextAction(index, action)
+                }
+            });
 
         let model = AppModel {
             sidebar_indexes: Default::default(),
@@ -537,9 +981,18 @@ impl Component for AppModel {
             output_path,
             output_format: actions::FormatInfo::PDF,
             status: Default::default(),
+            progress: Default::default(),
+            progress_pulse_source: None,
             is_showing_wizard: Default::default(),
             wizard_profiles,
+            wizard_place: Default::default(),
+            wizard_filter: Default::default(),
+            wizard_profile_fallback: None,
             background_worker,
+            smart_summary_busy: false,
+            smart_summary_request_id: 0,
+            smart_summary_enabled: smart_summary::is_configured(),
+            wide_layout: true,
         };
         let wizard_profile_list = model.wizard_profiles.widget();
         let sidebar_list = model.sidebar_list_items.widget();
@@ -551,6 +1004,18 @@ impl Component for AppModel {
             let sender = sender.input_sender().clone();
             move |_| sender.emit(AppInputMsg::PreviewChanged)
         });
+        link_rules::install_link_controllers(&widgets.preview);
+
+        for widget in plugin::ui_elements_for(plugin::UiSlot::Toolbar) {
+            widgets.plugin_toolbar_slot.append(&widget);
+        }
+        for widget in plugin::ui_elements_for(plugin::UiSlot::OutputOptions) {
+            widgets.plugin_output_options_slot.append(&widget);
+        }
+        plugin::install_keybindings(&widgets.window);
+
+        #[cfg(all(unix, feature = "search-provider"))]
+        search_provider::start(APP_ID, sender.input_sender().clone());
 
         ComponentParts { model, widgets }
     }
@@ -568,6 +1033,9 @@ impl Component for AppModel {
                 #[cfg(feature = "dark-theme")]
                 dark_theme::set_for_window(&widgets.window);
             }
+            AppInputMsg::WindowWidthChanged(width) => {
+                self.wide_layout = width >= NARROW_LAYOUT_WIDTH_THRESHOLD;
+            }
             // Mimic `Ctrl+left click` for regular `left click`.
             AppInputMsg::SidebarRowSelected(index) => {
                 let queued = self.sidebar_queued_activations;
@@ -633,47 +1101,204 @@ impl Component for AppModel {
             AppInputMsg::EditedInputPath => {
                 Self::update_path(&mut self.input_path, &widgets.input_path.text());
             }
+            AppInputMsg::SidebarRowContextAction(index, action) => {
+                let position = index.current_index();
+                let Some((open, group)) = self
+                    .sidebar_list_items
+                    .get(position)
+                    .and_then(|row| row.data.downcast_ref::<(bool, actions::TabGroup)>())
+                    .cloned()
+                else {
+                    return;
+                };
+
+                // A sidebar row already represents a whole window/group, so
+                // exporting/copying "just this row" means selecting only its
+                // own group index, the same way as a single-selection.
+                let single_group_options = actions::GenerateOptions {
+                    open_group_indexes: if open {
+                        Some(vec![group.index])
+                    } else {
+                        Some(Vec::new())
+                    },
+                    closed_group_indexes: if open {
+                        Some(Vec::new())
+                    } else {
+                        Some(vec![group.index])
+                    },
+                    sort_groups: true,
+                    table_of_content: false,
+                };
+
+                match action {
+                    RowContextAction::ExportOnlyThisWindow => {
+                        if let Some(data) = self.loaded_data.clone() {
+                            let save_path = self.output_path.clone();
+                            let output_options = actions::OutputOptions {
+                                format: self.output_format,
+                                overwrite: widgets.should_overwrite.is_active(),
+                                create_folder: widgets.should_create_folder.is_active(),
+                            };
+                            self.status = format!("Exporting \"{}\"", group.name);
+                            let sender = sender.command_sender().clone();
+                            self.queue_background_work(async move {
+                                sender.emit(AppCommandMsg::SetStatus(
+                                    match data
+                                        .save_links(save_path, single_group_options, output_options)
+                                        .await
+                                    {
+                                        Ok(()) => format!("Successfully exported \"{}\"", group.name),
+                                        Err(e) => format!("Failed to export window: {e}"),
+                                    },
+                                ));
+                            });
+                        }
+                    }
+                    RowContextAction::CopyThisWindowsLinks => {
+                        if let Some(data) = self.loaded_data.clone() {
+                            let sender = sender.command_sender().clone();
+                            self.queue_background_work(async move {
+                                match data.to_text_links(single_group_options).await {
+                                    Ok(text) => sender.emit(AppCommandMsg::CopyTextToClipboard(text)),
+                                    Err(e) => sender.emit(AppCommandMsg::SetStatus(format!(
+                                        "Failed to copy links: {e}"
+                                    ))),
+                                }
+                            });
+                        }
+                    }
+                    RowContextAction::RenameGroup => {
+                        if let Some(row) = widgets.sidebar_list.row_at_index(position as i32) {
+                            let entry = gtk::Entry::builder().text(&group.name).build();
+                            let popover = gtk::Popover::builder().child(&entry).autohide(true).build();
+                            popover.set_parent(&row);
+                            let input_sender = sender.input_sender().clone();
+                            let popover_clone = popover.clone();
+                            entry.connect_activate(move |entry| {
+                                input_sender.emit(AppInputMsg::RenameGroupCommitted(
+                                    index.clone(),
+                                    entry.text().to_string(),
+                                ));
+                                popover_clone.popdown();
+                            });
+                            popover.popup();
+                        }
+                    }
+                    RowContextAction::SelectAllTabsFromGroup => {
+                        widgets.sidebar_list.unselect_all();
+                        if let Some(row) = widgets.sidebar_list.row_at_index(position as i32) {
+                            widgets.sidebar_list.select_row(Some(&row));
+                        }
+                    }
+                }
+            }
+            AppInputMsg::RenameGroupCommitted(index, new_name) => {
+                let position = index.current_index();
+                if let Some(row) = self.sidebar_list_items.get(position) {
+                    if let Some((open, group)) =
+                        row.data.downcast_ref::<(bool, actions::TabGroup)>()
+                    {
+                        let groups = if *open {
+                            &mut self.tab_groups.open
+                        } else {
+                            &mut self.tab_groups.closed
+                        };
+                        if let Some(group) = groups.iter_mut().find(|g| g.index == group.index) {
+                            group.name = new_name;
+                        }
+                        // Sidebar-only: nothing downstream of `loaded_data`
+                        // reads this name, so don't emit `RegeneratePreview`
+                        // and imply the preview/export picks it up too.
+                        self.update_sidebar_list();
+                    }
+                }
+            }
             AppInputMsg::BrowseInput => {
                 let sender = sender.command_sender().clone();
                 self.queue_background_work(async move {
-                    if let Some(path) = actions::prompt_load_file().await {
+                    #[cfg(unix)]
+                    let path = if portal::is_sandboxed().await {
+                        portal::prompt_load_file().await
+                    } else {
+                        actions::prompt_load_file().await
+                    };
+                    #[cfg(not(unix))]
+                    let path = actions::prompt_load_file().await;
+
+                    if let Some(path) = path {
                         sender.emit(AppCommandMsg::SetInputPath(path));
                     }
                 });
             }
             AppInputMsg::OpenWizard => {
-                let mut profiles = self.wizard_profiles.guard();
-                profiles.clear();
-                for profile in actions::FirefoxProfileInfo::all_profiles() {
-                    profiles.push_back(RowLabelModel {
-                        name: profile.name().into_owned(),
-                        data: Box::new(profile),
-                        ..Default::default()
-                    });
+                self.wizard_place = WizardPlace::InstalledProfiles;
+                self.wizard_filter.clear();
+                widgets.wizard_filter_entry.set_text("");
+                // Reopening after a previous visit left a different place
+                // selected would otherwise show `InstalledProfiles` content
+                // under a stale highlighted row.
+                widgets
+                    .wizard_places_list
+                    .select_row(widgets.wizard_places_list.row_at_index(0).as_ref());
+
+                // Direct profile enumeration usually finds nothing inside a
+                // sandbox; fall back to a portal-granted location, reusing
+                // the one persisted by a previous prompt if we have it.
+                #[cfg(unix)]
+                if actions::FirefoxProfileInfo::all_profiles().is_empty() {
+                    if let Some(path) = portal::persisted_profile_fallback() {
+                        self.wizard_profile_fallback = Some(path);
+                    } else {
+                        let sender = sender.command_sender().clone();
+                        self.queue_background_work(async move {
+                            if portal::is_sandboxed().await {
+                                if let Some(path) = portal::prompt_profile_fallback().await {
+                                    sender.emit(AppCommandMsg::SetWizardProfileFallback(path));
+                                }
+                            }
+                        });
+                    }
                 }
+
+                self.populate_wizard_profiles();
                 self.is_showing_wizard = true;
             }
+            AppInputMsg::WizardPlaceSelected(place) => {
+                self.wizard_place = place;
+                self.populate_wizard_profiles();
+            }
+            AppInputMsg::WizardFilterChanged(filter) => {
+                self.wizard_filter = filter;
+                self.populate_wizard_profiles();
+            }
             AppInputMsg::SelectedWizardProfile(index) => {
                 if let Some(profile) = self.wizard_profiles.get(index as usize) {
-                    let profile = profile
+                    let path = profile
                         .data
                         .downcast_ref::<actions::FirefoxProfileInfo>()
-                        .expect("Failed to downcast profile wizard's list item data");
-
-                    sender.command_sender().emit(AppCommandMsg::SetInputPath(
-                        profile.find_sessionstore_file(),
-                    ));
-                    sender.input_sender().emit(AppInputMsg::LoadNewData);
+                        .map(|profile| profile.find_sessionstore_file())
+                        .or_else(|| profile.data.downcast_ref::<PathBuf>().cloned());
+
+                    if let Some(path) = path {
+                        sender
+                            .command_sender()
+                            .emit(AppCommandMsg::SetInputPath(path));
+                        sender.input_sender().emit(AppInputMsg::LoadNewData);
+                    }
                 }
             }
             AppInputMsg::CloseWizard => {
                 self.is_showing_wizard = false;
             }
             AppInputMsg::LoadNewData => {
+                recent_files::record_opened(&self.input_path);
+
                 let mut data = actions::FileInfo::new(self.input_path.clone());
                 self.loaded_data = Some(data.clone());
                 widgets.sidebar_list.unselect_all();
                 self.status = "Reading input file".to_string();
+                self.progress = Progress::Fraction(1.0 / LOAD_PIPELINE_PHASES);
+                widgets.progress_bar.set_fraction(1.0 / LOAD_PIPELINE_PHASES);
 
                 let sender = sender.command_sender().clone();
                 self.queue_background_work(async move {
@@ -703,7 +1328,16 @@ impl Component for AppModel {
             AppInputMsg::BrowseOutput => {
                 let sender = sender.command_sender().clone();
                 self.queue_background_work(async move {
-                    if let Some(path) = actions::prompt_save_file().await {
+                    #[cfg(unix)]
+                    let path = if portal::is_sandboxed().await {
+                        portal::prompt_save_file().await
+                    } else {
+                        actions::prompt_save_file().await
+                    };
+                    #[cfg(not(unix))]
+                    let path = actions::prompt_save_file().await;
+
+                    if let Some(path) = path {
                         sender.emit(AppCommandMsg::SetOutputPath(path));
                     }
                 });
@@ -735,18 +1369,50 @@ impl Component for AppModel {
                     };
 
                     self.status = "Saving links to file".to_string();
+                    self.progress = Progress::Indeterminate;
+                    self.start_progress_pulse(widgets);
 
                     let sender = sender.command_sender().clone();
                     self.queue_background_work(async move {
-                        sender.emit(AppCommandMsg::SetStatus(
-                            match data.save_links(save_path, selected, output_options).await {
-                                Ok(()) => "Successfully saved links to a file".to_string(),
-                                Err(e) => format!("Failed to save links to file: {e}"),
-                            },
-                        ));
+                        match data.save_links(save_path, selected, output_options).await {
+                            Ok(()) => {
+                                sender.emit(AppCommandMsg::SetStatus(
+                                    "Successfully saved links to a file".to_string(),
+                                ));
+                                sender.emit(AppCommandMsg::NotifyExportCompleted);
+                            }
+                            Err(e) => sender.emit(AppCommandMsg::SetStatus(format!(
+                                "Failed to save links to file: {e}"
+                            ))),
+                        }
                     });
                 };
             }
+            AppInputMsg::RequestSmartSummary => {
+                if let Some(data) = self.loaded_data.clone() {
+                    let options = self.selected_tab_groups(widgets);
+
+                    self.smart_summary_busy = true;
+                    self.smart_summary_request_id += 1;
+                    let request_id = self.smart_summary_request_id;
+                    // `oneshot_command` (unlike `queue_background_work`) is
+                    // never dropped by some other, unrelated action queuing
+                    // work afterwards, so this always gets a chance to clear
+                    // `smart_summary_busy` again; `request_id` is still
+                    // checked on arrival in case a second click landed
+                    // before the button became insensitive.
+                    sender.oneshot_command(async move {
+                        AppCommandMsg::SmartSummaryResult(
+                            request_id,
+                            smart_summary::summarize_selection(&data, options).await,
+                        )
+                    });
+                }
+            }
+            AppInputMsg::FocusWithQuery(query) => {
+                self.status = format!("Search: {query}");
+                widgets.window.present();
+            }
         }
 
         self.update_view(widgets, sender);
@@ -775,6 +1441,8 @@ impl Component for AppModel {
                 match &data.data {
                     Some(FileData::Compressed { .. }) => {
                         self.status = "Decompressing data".to_string();
+                        self.progress = Progress::Indeterminate;
+                        self.start_progress_pulse(widgets);
                         let sender = sender.command_sender().clone();
                         self.queue_background_work(async move {
                             sender.emit(match data.decompress_data().await {
@@ -787,6 +1455,9 @@ impl Component for AppModel {
                     }
                     Some(FileData::Uncompressed { .. }) => {
                         self.status = "Parsing session data".to_string();
+                        self.stop_progress_pulse();
+                        self.progress = Progress::Fraction(2.0 / LOAD_PIPELINE_PHASES);
+                        widgets.progress_bar.set_fraction(2.0 / LOAD_PIPELINE_PHASES);
                         let sender = sender.command_sender().clone();
                         self.queue_background_work(async move {
                             sender.emit(match data.parse_session_data().await {
@@ -799,6 +1470,8 @@ impl Component for AppModel {
                     }
                     Some(FileData::Parsed { .. }) => {
                         self.status = "Searching for tab groups".to_owned();
+                        self.progress = Progress::Fraction(3.0 / LOAD_PIPELINE_PHASES);
+                        widgets.progress_bar.set_fraction(3.0 / LOAD_PIPELINE_PHASES);
                         let sender = sender.command_sender().clone();
                         self.queue_background_work(async move {
                             sender.emit(match data.get_groups_from_session(true).await {
@@ -815,6 +1488,21 @@ impl Component for AppModel {
             AppCommandMsg::ParsedTabGroups(all_groups) => {
                 self.tab_groups = all_groups;
                 self.update_sidebar_list();
+                plugin::emit(plugin::AppEvent::DataLoaded);
+
+                if let Some(data) = self.loaded_data.clone() {
+                    sender.oneshot_command(async move {
+                        let options = actions::GenerateOptions {
+                            open_group_indexes: None,
+                            closed_group_indexes: None,
+                            sort_groups: true,
+                            table_of_content: false,
+                        };
+                        AppCommandMsg::UpdateSearchIndex(
+                            data.to_text_links(options).await.unwrap_or_default(),
+                        )
+                    });
+                }
 
                 sender
                     .command_sender()
@@ -823,6 +1511,8 @@ impl Component for AppModel {
             AppCommandMsg::RegeneratePreview => {
                 if let Some(data) = self.loaded_data.clone() {
                     self.status = "Generating preview".to_string();
+                    self.progress = Progress::Fraction(4.0 / LOAD_PIPELINE_PHASES);
+                    widgets.progress_bar.set_fraction(4.0 / LOAD_PIPELINE_PHASES);
                     let options = self.selected_tab_groups(widgets);
                     let sender = sender.command_sender().clone();
                     self.queue_background_work(async move {
@@ -839,7 +1529,11 @@ impl Component for AppModel {
             }
             AppCommandMsg::SetPreview(text) => {
                 self.status = "Successfully loaded session data".to_string();
-                widgets.preview.buffer().set_text(&text);
+                self.progress = Progress::Idle;
+                let buffer = widgets.preview.buffer();
+                buffer.set_text(&text);
+                link_rules::apply_link_rules(&buffer);
+                plugin::emit(plugin::AppEvent::PreviewRegenerated);
             }
             AppCommandMsg::FixPreviewScrollbar => {
                 widgets
@@ -847,7 +1541,43 @@ impl Component for AppModel {
                     .set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
             }
             AppCommandMsg::SetStatus(status) => {
+                // Every `SetStatus` is emitted from a background task's
+                // terminal `Ok`/`Err` branch, so it always marks the end of
+                // whatever operation was in progress.
                 self.status = status;
+                self.stop_progress_pulse();
+                self.progress = Progress::Idle;
+            }
+            AppCommandMsg::NotifyExportCompleted => {
+                plugin::emit(plugin::AppEvent::ExportCompleted);
+            }
+            AppCommandMsg::SetWizardProfileFallback(path) => {
+                self.wizard_profile_fallback = Some(path);
+                if self.wizard_place == WizardPlace::InstalledProfiles {
+                    self.populate_wizard_profiles();
+                }
+            }
+            AppCommandMsg::CopyTextToClipboard(text) => {
+                let display = gdk::Display::default().expect("GTK display not found");
+                display.clipboard().set_text(&text);
+                self.status = "Copied links to clipboard".to_string();
+            }
+            AppCommandMsg::SmartSummaryResult(request_id, result) => {
+                // Ignore a stale result from a request that's since been
+                // superseded by a newer one.
+                if request_id == self.smart_summary_request_id {
+                    self.smart_summary_busy = false;
+                    self.status = match result {
+                        Ok(Some(summary)) => format!("Smart Summary: {summary}"),
+                        Ok(None) => "Smart Summary is not configured (set SMART_SUMMARY_BASE_URL)"
+                            .to_string(),
+                        Err(e) => format!("Smart Summary failed: {e}"),
+                    };
+                }
+            }
+            AppCommandMsg::UpdateSearchIndex(_markdown) => {
+                #[cfg(all(unix, feature = "search-provider"))]
+                search_provider::update_index(&_markdown);
             }
         }
         self.update_view(widgets, sender);
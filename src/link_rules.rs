@@ -0,0 +1,170 @@
+//! Makes the preview [`gtk::TextView`] clickable: matched text (by default
+//! just `http(s)://` URLs) gets underlined and opens its target on click.
+//!
+//! New kinds of links (e.g. `about:` pages, `file:` links) can be supported
+//! without touching the view itself by registering another [`LinkRule`] with
+//! [`register_rule`].
+
+use std::sync::{LazyLock, Mutex};
+
+use gtk::{gdk, gio, glib, prelude::*};
+use regex::Regex;
+use relm4::gtk;
+
+/// Name of a shared `gtk::TextTag` kept in the buffer's tag table and
+/// cleared from the whole buffer on every re-scan. Matched links are marked
+/// with per-match tags instead (see [`MATCH_TAGS`]), and links are
+/// recognized by the target stored under [`LINK_TARGET_KEY`].
+const LINK_TAG_NAME: &str = "link";
+/// Key used to stash the matched target string on a tagged text range via
+/// `glib`'s per-object qdata, since `gtk::TextTag` has no custom fields.
+const LINK_TARGET_KEY: &str = "firefox-session-ui-link-target";
+
+/// One pluggable kind of link that can appear in the preview text.
+pub struct LinkRule {
+    /// Matched against the whole preview text; the first capture group (or
+    /// the whole match if the pattern has none) becomes the link's target.
+    pub pattern: Regex,
+    /// Invoked with the matched target when the user clicks the link.
+    pub on_click: fn(&str),
+}
+
+static RULES: LazyLock<Mutex<Vec<LinkRule>>> = LazyLock::new(|| Mutex::new(default_rules()));
+
+/// Per-match tags added by the last [`apply_link_rules`] call, so they can
+/// be removed from the tag table before the next re-scan instead of
+/// accumulating there for the lifetime of the app.
+static MATCH_TAGS: Mutex<Vec<gtk::TextTag>> = Mutex::new(Vec::new());
+
+fn default_rules() -> Vec<LinkRule> {
+    vec![LinkRule {
+        pattern: Regex::new(r"[a-zA-Z][a-zA-Z0-9+.-]*://[^\s<>\[\]()]+").unwrap(),
+        on_click: open_url,
+    }]
+}
+
+/// Register an additional link rule. Rules are tried in registration order,
+/// and a later match wins over an earlier overlapping one.
+pub fn register_rule(rule: LinkRule) {
+    RULES.lock().unwrap().push(rule);
+}
+
+pub(crate) fn open_url(target: &str) {
+    let context = gdk::Display::default().map(|display| display.app_launch_context());
+    if let Err(e) = gio::AppInfo::launch_default_for_uri(target, context.as_ref()) {
+        eprintln!("Failed to open link {target:?}: {e}");
+    }
+}
+
+/// Re-scan `buffer`'s full text against every registered rule and tag every
+/// match so it renders as an underlined, accent-colored, clickable link.
+/// Call this after the preview text has been replaced.
+pub fn apply_link_rules(buffer: &gtk::TextBuffer) {
+    let tag_table = buffer.tag_table();
+    let tag = match tag_table.lookup(LINK_TAG_NAME) {
+        Some(tag) => tag,
+        None => {
+            let tag = gtk::TextTag::builder()
+                .name(LINK_TAG_NAME)
+                .underline(gtk::pango::Underline::Single)
+                .foreground("#3584e4") // GNOME accent blue.
+                .build();
+            tag_table.add(&tag);
+            tag
+        }
+    };
+
+    let start = buffer.start_iter();
+    let end = buffer.end_iter();
+    buffer.remove_tag(&tag, &start, &end);
+
+    // Drop the per-match tags added by the previous scan instead of letting
+    // them (and the qdata `String` each one carries) pile up in the tag
+    // table forever.
+    let mut match_tags = MATCH_TAGS.lock().unwrap();
+    for old_tag in match_tags.drain(..) {
+        tag_table.remove(&old_tag);
+    }
+
+    let text = buffer.text(&start, &end, false);
+    let rules = RULES.lock().unwrap();
+    for rule in rules.iter() {
+        for capture in rule.pattern.captures_iter(&text) {
+            let whole = capture.get(0).unwrap();
+            let target = capture
+                .get(1)
+                .unwrap_or(whole)
+                .as_str()
+                .to_owned();
+
+            let match_start = buffer.iter_at_offset(byte_offset_to_char_offset(&text, whole.start()));
+            let match_end = buffer.iter_at_offset(byte_offset_to_char_offset(&text, whole.end()));
+
+            let link_tag = gtk::TextTag::builder()
+                .underline(gtk::pango::Underline::Single)
+                .foreground("#3584e4")
+                .build();
+            unsafe {
+                link_tag.set_data(LINK_TARGET_KEY, target);
+            }
+            tag_table.add(&link_tag);
+            buffer.apply_tag(&link_tag, &match_start, &match_end);
+            match_tags.push(link_tag);
+        }
+    }
+}
+
+/// `regex` byte offsets need converting to the char offsets that
+/// `TextBuffer::iter_at_offset` expects.
+fn byte_offset_to_char_offset(text: &str, byte_offset: usize) -> i32 {
+    text[..byte_offset].chars().count() as i32
+}
+
+/// The target stashed on `iter`'s link tag (if any), for use by both the
+/// click handler and the hover/cursor handler.
+fn link_target_at_iter(iter: &gtk::TextIter) -> Option<String> {
+    // `tags()` lists tags in ascending priority, and tags added later (i.e.
+    // by later rules) get higher priority, so search from the end.
+    iter.tags().into_iter().rev().find_map(|tag| unsafe {
+        tag.data::<String>(LINK_TARGET_KEY)
+            .map(|ptr| ptr.as_ref().clone())
+    })
+}
+
+/// Wire up click-to-open and a pointer cursor on hover for any links tagged
+/// by [`apply_link_rules`]. Call this once when the preview view is created.
+pub fn install_link_controllers(view: &gtk::TextView) {
+    let click = gtk::GestureClick::new();
+    click.connect_released({
+        let view = view.clone();
+        move |_gesture, _n_press, x, y| {
+            let (buf_x, buf_y) =
+                view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+            if let Some(iter) = view.iter_at_location(buf_x, buf_y) {
+                if let Some(target) = link_target_at_iter(&iter) {
+                    let rules = RULES.lock().unwrap();
+                    if let Some(rule) = rules.iter().rev().find(|rule| rule.pattern.is_match(&target)) {
+                        (rule.on_click)(&target);
+                    } else {
+                        open_url(&target);
+                    }
+                }
+            }
+        }
+    });
+    view.add_controller(click);
+
+    let motion = gtk::EventControllerMotion::new();
+    motion.connect_motion({
+        let view = view.clone();
+        move |_controller, x, y| {
+            let (buf_x, buf_y) =
+                view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+            let is_link = view
+                .iter_at_location(buf_x, buf_y)
+                .is_some_and(|iter| link_target_at_iter(&iter).is_some());
+            view.set_cursor_from_name(Some(if is_link { "pointer" } else { "text" }));
+        }
+    });
+    view.add_controller(motion);
+}
@@ -0,0 +1,115 @@
+//! Extensibility layer: third parties can implement [`Plugin`] to add their
+//! own actions without forking this crate. A plugin can request a UI
+//! slot for its own widget, declare keyboard shortcuts, and subscribe to a
+//! broadcast stream of [`AppEvent`]s mirroring the interesting parts of
+//! [`crate::AppCommandMsg`]. A plugin cannot add entries to the output
+//! format dropdown; that list always comes from `actions::FormatInfo::all`.
+
+use std::sync::{Mutex, OnceLock};
+
+use gtk::prelude::*;
+use relm4::gtk;
+use tokio::sync::broadcast;
+
+/// Application-level events plugins can react to.
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    DataLoaded,
+    PreviewRegenerated,
+    ExportCompleted,
+}
+
+/// Where in the UI a plugin's widget (see [`Plugin::get_ui_element`]) should
+/// be placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiSlot {
+    /// Toolbar box right after the "Copy links to clipboard" and Smart
+    /// Summary buttons, at the opposite end of the row from "Save links to
+    /// file".
+    Toolbar,
+    /// Area beside the output-format `DropDown`.
+    OutputOptions,
+}
+
+/// Implemented by third-party plugins to extend the app without forking it.
+pub trait Plugin: Send + Sync {
+    /// Optional widget to inject at [`Self::ui_slot`].
+    fn get_ui_element(&self) -> Option<gtk::Widget> {
+        None
+    }
+
+    /// Where [`Self::get_ui_element`]'s widget (if any) should be placed.
+    fn ui_slot(&self) -> UiSlot {
+        UiSlot::Toolbar
+    }
+
+    /// Accelerator/action-name pairs to install on the application window,
+    /// e.g. `("<Control>e".to_owned(), "win.export".to_owned())`.
+    fn bind_keys(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// React to an application event. Called synchronously on the main
+    /// thread from wherever [`emit`] is invoked.
+    fn on_event(&self, _event: &AppEvent) {}
+}
+
+static PLUGINS: Mutex<Vec<Box<dyn Plugin>>> = Mutex::new(Vec::new());
+static EVENTS: OnceLock<broadcast::Sender<AppEvent>> = OnceLock::new();
+
+fn events() -> &'static broadcast::Sender<AppEvent> {
+    EVENTS.get_or_init(|| broadcast::channel(16).0)
+}
+
+/// Register a plugin. Call this during app startup, before the main window's
+/// `AppModel::init` runs, so its UI slot/keybindings are picked up.
+pub fn register(plugin: Box<dyn Plugin>) {
+    PLUGINS.lock().unwrap().push(plugin);
+}
+
+/// Subscribe to the broadcast stream of [`AppEvent`]s; mainly useful for a
+/// plugin that wants to react asynchronously rather than via
+/// [`Plugin::on_event`].
+pub fn subscribe() -> broadcast::Receiver<AppEvent> {
+    events().subscribe()
+}
+
+/// Notify every registered plugin (both synchronously via
+/// [`Plugin::on_event`] and any [`subscribe`] listeners) that `event`
+/// happened.
+pub fn emit(event: AppEvent) {
+    let _ = events().send(event.clone());
+    for plugin in PLUGINS.lock().unwrap().iter() {
+        plugin.on_event(&event);
+    }
+}
+
+/// Collect every registered plugin's widget for `slot`, in registration
+/// order, so the app can append them to the corresponding slot container.
+pub fn ui_elements_for(slot: UiSlot) -> Vec<gtk::Widget> {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|plugin| plugin.ui_slot() == slot)
+        .filter_map(|plugin| plugin.get_ui_element())
+        .collect()
+}
+
+/// Install every registered plugin's declared accelerators on `window` via a
+/// `gtk::ShortcutController`. Call once, after all plugins are registered.
+pub fn install_keybindings(window: &impl IsA<gtk::Window>) {
+    let controller = gtk::ShortcutController::new();
+    controller.set_scope(gtk::ShortcutScope::Global);
+    for plugin in PLUGINS.lock().unwrap().iter() {
+        for (accelerator, action_name) in plugin.bind_keys() {
+            let Some(trigger) = gtk::ShortcutTrigger::parse_string(&accelerator) else {
+                eprintln!("Plugin declared an invalid accelerator: {accelerator:?}");
+                continue;
+            };
+            let action = gtk::NamedAction::new(&action_name);
+            controller.add_shortcut(&gtk::Shortcut::new(Some(trigger), Some(action.upcast())));
+        }
+    }
+    window.add_controller(controller);
+}
@@ -0,0 +1,104 @@
+//! XDG Desktop Portal backend for file selection, used instead of
+//! `actions::prompt_load_file`/`prompt_save_file` when running inside a
+//! sandbox (e.g. Flatpak), where those rely on direct host filesystem
+//! access that isn't available there. [`crate::AppInputMsg::BrowseInput`]/
+//! [`crate::AppInputMsg::BrowseOutput`] pick this backend at runtime via
+//! [`is_sandboxed`], so the native `gtk::FileDialog` path in `actions` keeps
+//! working unchanged outside a sandbox.
+//!
+//! The `FileChooser` portal's `OpenFile`/`SaveFile` methods hand back plain
+//! URIs, not file descriptors (that's the D-Bus interface the portal
+//! exposes — there's no fd-passing variant to ask for instead), so reading
+//! or writing through the granted document still goes through whatever
+//! `gio` resolves that URI to. We go through [`gio::File`] rather than
+//! naively parsing the URI as a `file://` path ourselves: that's the layer
+//! that actually understands the document portal's mount rather than
+//! assuming a plain POSIX path always exists, so it degrades correctly in
+//! sandboxes where the FUSE document mount isn't present (resolving to
+//! `None` instead of silently handing back a path that doesn't work).
+//!
+//! The profile wizard (`AppInputMsg::OpenWizard`) falls back to
+//! [`prompt_profile_fallback`] when `actions::FirefoxProfileInfo::all_profiles`'s
+//! direct `~/.mozilla`/AppData enumeration finds nothing, which is the usual
+//! case inside a sandbox. The picked file only needs to reach the wizard as
+//! a `PathBuf`, the same way a [`crate::recent_files`] entry does, so this
+//! doesn't need a way to build a full `FirefoxProfileInfo` from an arbitrary
+//! path.
+
+use std::path::PathBuf;
+
+use ashpd::desktop::file_chooser::{FileFilter, OpenFileRequest, SaveFileRequest};
+use relm4::gtk::gio;
+
+use crate::recent_files;
+
+/// Whether we're running inside a sandbox that needs the portal backend
+/// instead of direct filesystem access.
+pub async fn is_sandboxed() -> bool {
+    ashpd::is_sandboxed().await
+}
+
+/// Resolve a portal-granted URI to a local path via `gio`, which
+/// understands the document portal's FUSE mount instead of assuming the
+/// URI is a plain `file://` path.
+fn local_path_for_uri(uri: &ashpd::url::Url) -> Option<PathBuf> {
+    gio::File::for_uri(uri.as_str()).path()
+}
+
+/// Portal equivalent of `actions::prompt_load_file`: ask the `FileChooser`
+/// portal's `OpenFile` method for a session data file.
+pub async fn prompt_load_file() -> Option<PathBuf> {
+    let filter = FileFilter::new("Firefox session data").glob("sessionstore*");
+    let selected = OpenFileRequest::default()
+        .title("Open Firefox session data")
+        .filter(filter)
+        .send()
+        .await
+        .and_then(|request| request.response())
+        .ok()?;
+    local_path_for_uri(selected.uris().first()?)
+}
+
+/// Portal equivalent of `actions::prompt_save_file`: ask the `FileChooser`
+/// portal's `SaveFile` method for an output path.
+pub async fn prompt_save_file() -> Option<PathBuf> {
+    let selected = SaveFileRequest::default()
+        .title("Save links to file")
+        .send()
+        .await
+        .and_then(|request| request.response())
+        .ok()?;
+    local_path_for_uri(selected.uris().first()?)
+}
+
+/// Where [`prompt_profile_fallback`] persists the path it picked, so the
+/// profile wizard doesn't need to reprompt on every launch.
+fn profile_fallback_file() -> Option<PathBuf> {
+    let mut dir = recent_files::config_dir()?;
+    dir.push("firefox-session-ui-gtk4");
+    std::fs::create_dir_all(&dir).ok()?;
+    dir.push("sandboxed-profile-location.txt");
+    Some(dir)
+}
+
+/// The profile location persisted by a previous [`prompt_profile_fallback`]
+/// call, if its target still exists. The document portal's own grant for a
+/// previously opened file persists across restarts on its own; all this
+/// needs to remember is which file that was.
+pub fn persisted_profile_fallback() -> Option<PathBuf> {
+    let path = std::fs::read_to_string(profile_fallback_file()?).ok()?;
+    let path = PathBuf::from(path.trim());
+    path.exists().then_some(path)
+}
+
+/// Ask the `FileChooser` portal for a Firefox profile's session data file,
+/// for the profile wizard to fall back to when direct `~/.mozilla`/AppData
+/// enumeration finds nothing. Persists the choice for
+/// [`persisted_profile_fallback`] to pick up on future launches.
+pub async fn prompt_profile_fallback() -> Option<PathBuf> {
+    let path = prompt_load_file().await?;
+    if let Some(store) = profile_fallback_file() {
+        let _ = std::fs::write(store, path.to_string_lossy().as_bytes());
+    }
+    Some(path)
+}
@@ -0,0 +1,73 @@
+//! Tracks recently opened session files so the profile wizard's "Recent
+//! Session Files" place (see [`crate::WizardPlace`]) has something to show
+//! besides installed profiles. Persisted as a plain newline-separated list
+//! under the user's config directory rather than pulling in a settings
+//! framework for ten paths.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// How many recent entries to remember.
+const MAX_ENTRIES: usize = 10;
+
+/// This app's config directory; `pub(crate)` so [`crate::portal`] can store
+/// its own persisted portal grant alongside this module's recent-files list
+/// without duplicating the platform lookup.
+#[cfg(windows)]
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+#[cfg(not(windows))]
+pub(crate) fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+fn list_file() -> Option<PathBuf> {
+    let mut dir = config_dir()?;
+    dir.push("firefox-session-ui-gtk4");
+    fs::create_dir_all(&dir).ok()?;
+    dir.push("recent-session-files.txt");
+    Some(dir)
+}
+
+/// Every recently opened session file, most recent first, that still
+/// exists on disk.
+pub fn list() -> Vec<PathBuf> {
+    let Some(path) = list_file() else {
+        return Vec::new();
+    };
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Record `path` as the most recently opened session file, moving it to the
+/// front if already present and trimming the list to [`MAX_ENTRIES`]. Call
+/// this from [`crate::AppInputMsg::LoadNewData`].
+pub fn record_opened(path: &Path) {
+    let Some(list_path) = list_file() else {
+        return;
+    };
+
+    let mut entries = list();
+    entries.retain(|existing| existing != path);
+    entries.insert(0, path.to_owned());
+    entries.truncate(MAX_ENTRIES);
+
+    let Ok(mut file) = fs::File::create(list_path) else {
+        return;
+    };
+    for entry in entries {
+        let _ = writeln!(file, "{}", entry.display());
+    }
+}
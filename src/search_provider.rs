@@ -0,0 +1,164 @@
+//! `org.gnome.Shell.SearchProvider2` integration: once a session file has
+//! been parsed, its tabs become searchable from the GNOME Activities
+//! overview, the same way distro-packaged Firefox exposes open tabs. See
+//! `data/lej77.firefox-session-ui.gtk4.search-provider.ini` for how GNOME
+//! Shell discovers the bus name/object path used here, and the matching
+//! `.service` file for D-Bus activation.
+//!
+//! Activating a result or launching a search still requires the app window
+//! to be shown (it isn't a truly headless search backend), but D-Bus
+//! activation does mean the app starts on demand rather than needing to
+//! already be running.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use regex::Regex;
+use relm4::Sender;
+use zbus::zvariant::Value;
+
+use crate::AppInputMsg;
+
+/// One searchable tab. Built from the same markdown links text used for the
+/// preview (see [`update_index`]) rather than from `actions::TabGroup`
+/// directly, so this module doesn't need to assume tab-level field names.
+#[derive(Debug, Clone)]
+struct TabEntry {
+    /// Opaque result id handed back by `GetInitialResultSet`/
+    /// `GetSubsearchResultSet` and later looked up in `GetResultMetas`/
+    /// `ActivateResult`.
+    id: String,
+    title: String,
+    url: String,
+}
+
+static INDEX: Mutex<Vec<TabEntry>> = Mutex::new(Vec::new());
+static INPUT_SENDER: OnceLock<Sender<AppInputMsg>> = OnceLock::new();
+
+fn link_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\[([^\]]*)\]\(([^)]*)\)").unwrap())
+}
+
+/// Rebuild the searchable index from `markdown`, the same rendered links
+/// text produced for the preview. Call this whenever
+/// `AppCommandMsg::ParsedTabGroups` updates `AppModel::tab_groups`.
+pub fn update_index(markdown: &str) {
+    let entries = link_pattern()
+        .captures_iter(markdown)
+        .enumerate()
+        .map(|(i, capture)| TabEntry {
+            id: i.to_string(),
+            title: capture[1].to_owned(),
+            url: capture[2].to_owned(),
+        })
+        .collect();
+    *INDEX.lock().unwrap() = entries;
+}
+
+fn search(terms: &[String], within: Option<&[String]>) -> Vec<String> {
+    let index = INDEX.lock().unwrap();
+    index
+        .iter()
+        .filter(|entry| match within {
+            Some(ids) => ids.contains(&entry.id),
+            None => true,
+        })
+        .filter(|entry| {
+            let haystack = format!("{} {}", entry.title, entry.url).to_lowercase();
+            terms
+                .iter()
+                .all(|term| haystack.contains(&term.to_lowercase()))
+        })
+        .map(|entry| entry.id.clone())
+        .collect()
+}
+
+/// Handler for the `org.gnome.Shell.SearchProvider2` D-Bus interface, backed
+/// by the global [`INDEX`].
+struct SearchProvider;
+
+#[zbus::interface(name = "org.gnome.Shell.SearchProvider2")]
+impl SearchProvider {
+    async fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+        search(&terms, None)
+    }
+
+    async fn get_subsearch_result_set(
+        &self,
+        previous_results: Vec<String>,
+        terms: Vec<String>,
+    ) -> Vec<String> {
+        search(&terms, Some(&previous_results))
+    }
+
+    async fn get_result_metas(&self, identifiers: Vec<String>) -> Vec<HashMap<String, Value<'_>>> {
+        let index = INDEX.lock().unwrap();
+        identifiers
+            .iter()
+            .filter_map(|id| index.iter().find(|entry| &entry.id == id))
+            .map(|entry| {
+                HashMap::from([
+                    ("id".to_owned(), Value::from(entry.id.clone())),
+                    ("name".to_owned(), Value::from(entry.title.clone())),
+                    ("description".to_owned(), Value::from(entry.url.clone())),
+                    // "gicon" expects a `GIcon`'s serialized string form (what
+                    // `g_icon_to_string()`/`g_icon_new_for_string()` produce);
+                    // for a plain themed icon name that's just the name
+                    // itself, but the shell only looks for it under "gicon",
+                    // not "icon".
+                    ("gicon".to_owned(), Value::from("web-browser-symbolic")),
+                ])
+            })
+            .collect()
+    }
+
+    async fn activate_result(&self, identifier: String, _terms: Vec<String>, _timestamp: u32) {
+        let url = INDEX
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.id == identifier)
+            .map(|entry| entry.url.clone());
+        if let Some(url) = url {
+            crate::link_rules::open_url(&url);
+        }
+    }
+
+    async fn launch_search(&self, terms: Vec<String>, _timestamp: u32) {
+        if let Some(sender) = INPUT_SENDER.get() {
+            sender.emit(AppInputMsg::FocusWithQuery(terms.join(" ")));
+        }
+    }
+}
+
+/// Record the input sender `LaunchSearch` uses to talk back to the running
+/// [`crate::AppModel`] (`ActivateResult` opens the URL directly without
+/// going through the app), and start serving the interface on
+/// the session bus. Call once from [`crate::AppModel::init`]; errors (e.g.
+/// running outside a session bus) are logged and otherwise ignored, same as
+/// the other optional OS-integration subsystems in this crate.
+pub fn start(app_id: &str, input_sender: Sender<AppInputMsg>) {
+    let _ = INPUT_SENDER.set(input_sender);
+
+    let bus_name = format!("{app_id}.SearchProvider");
+    let object_path = format!("/{}/SearchProvider", app_id.replace(['.', '-'], "/"));
+    tokio::task::spawn(async move {
+        let result: zbus::Result<()> = async {
+            zbus::connection::Builder::session()?
+                .name(bus_name.as_str())?
+                .serve_at(object_path.as_str(), SearchProvider)?
+                .build()
+                .await?;
+            // Keep the connection (and its registered object) alive.
+            std::future::pending::<()>().await;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            eprintln!("Failed to start GNOME Shell search provider: {e}");
+        }
+    });
+}
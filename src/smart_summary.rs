@@ -0,0 +1,111 @@
+//! Optional "Smart Summary" subsystem: asks a configurable OpenAI-compatible
+//! completion endpoint to suggest a short, human-readable name for the
+//! currently selected tab group(s), derived from their rendered tab titles
+//! and URLs. Degrades to a no-op when no endpoint is configured, so the app
+//! behaves exactly as before unless the user opts in.
+
+use std::env;
+
+use serde::Deserialize;
+
+use crate::actions::{FileInfo, GenerateOptions};
+
+/// Read from `SMART_SUMMARY_BASE_URL`/`SMART_SUMMARY_MODEL`/
+/// `SMART_SUMMARY_API_KEY`. `None` (feature disabled) if no base URL is set.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+impl Config {
+    pub fn from_env() -> Option<Self> {
+        let base_url = env::var("SMART_SUMMARY_BASE_URL").ok()?;
+        let model =
+            env::var("SMART_SUMMARY_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_owned());
+        let api_key = env::var("SMART_SUMMARY_API_KEY").ok();
+        Some(Self {
+            base_url,
+            model,
+            api_key,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Generate a short name/summary for the tab groups selected by `options`,
+/// by first rendering them to the same markdown links text used for the
+/// preview, then asking the completion endpoint to summarize that text.
+///
+/// Returns `Ok(None)` when no endpoint is configured, so callers can
+/// silently skip showing a result rather than surfacing an error.
+pub async fn summarize_selection(
+    data: &FileInfo,
+    options: GenerateOptions,
+) -> Result<Option<String>, String> {
+    let Some(config) = Config::from_env() else {
+        return Ok(None);
+    };
+
+    let tabs_text = data
+        .to_text_links(options)
+        .await
+        .map_err(|e| format!("failed to render selection: {e}"))?;
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .post(format!(
+            "{}/chat/completions",
+            config.base_url.trim_end_matches('/')
+        ))
+        .json(&serde_json::json!({
+            "model": config.model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": "Suggest a short, human-readable name (a few words) for a \
+                                 browser window based on a list of its open tabs. Respond \
+                                 with only the name, no punctuation or quotes.",
+                },
+                { "role": "user", "content": tabs_text },
+            ],
+        }));
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("request failed: {e}"))?
+        .json::<ChatCompletionResponse>()
+        .await
+        .map_err(|e| format!("failed to parse response: {e}"))?;
+
+    response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| Some(choice.message.content.trim().to_owned()))
+        .ok_or_else(|| "response contained no choices".to_owned())
+}
+
+/// `true` if a Smart Summary endpoint is configured, so the UI can decide
+/// whether to show the feature at all.
+pub fn is_configured() -> bool {
+    Config::from_env().is_some()
+}